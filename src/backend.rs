@@ -0,0 +1,245 @@
+//! Terminal backend selection. `crossterm` is the default; enable the
+//! `termion` or `rustbox` Cargo feature to run on those terminals instead.
+//! Each backend sets up/tears down its own raw-mode terminal and translates
+//! its own key events into the crate-wide [`Key`], but feeds the same
+//! `Event<Key>` channel the rest of the draw loop already consumes.
+
+use std::error::Error;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use crate::Event;
+
+/// A backend-agnostic key, translated from whichever terminal library is active.
+#[derive(Debug, Clone, Copy)]
+pub enum Key {
+    Char(char),
+    Other,
+}
+
+#[cfg(not(any(feature = "termion", feature = "rustbox")))]
+mod imp {
+    use super::*;
+    use crossterm::event::{self, Event as CEvent, KeyCode};
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use std::io::stdout;
+    use tui::backend::CrosstermBackend;
+    use tui::Terminal;
+
+    pub type Backend = CrosstermBackend<std::io::Stdout>;
+
+    pub fn setup_terminal() -> Result<Terminal<Backend>, Box<dyn Error>> {
+        enable_raw_mode().expect("can run in raw mode");
+        let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+        Ok(terminal)
+    }
+
+    pub fn teardown_terminal() -> Result<(), Box<dyn Error>> {
+        disable_raw_mode()?;
+        Ok(())
+    }
+
+    pub fn spawn_input_thread(tx: Sender<Event<Key>>, tick_rate: Duration) {
+        std::thread::spawn(move || {
+            let mut last_tick = Instant::now();
+            loop {
+                let timeout = tick_rate
+                    .checked_sub(last_tick.elapsed())
+                    .unwrap_or_else(|| Duration::from_millis(0));
+
+                if event::poll(timeout).expect("poll works") {
+                    if let CEvent::Key(key) = event::read().expect("can read events") {
+                        let key = match key.code {
+                            KeyCode::Char(c) => Key::Char(c),
+                            _ => Key::Other,
+                        };
+                        tx.send(Event::Input(key)).expect("can send events");
+                    }
+                }
+
+                if last_tick.elapsed() >= tick_rate && tx.send(Event::Tick).is_ok() {
+                    last_tick = Instant::now();
+                }
+            }
+        });
+    }
+}
+
+#[cfg(feature = "termion")]
+mod imp {
+    use super::*;
+    use std::io::stdout;
+    use std::sync::mpsc;
+    use termion::event::Key as TKey;
+    use termion::input::TermRead;
+    use termion::raw::{IntoRawMode, RawTerminal};
+    use tui::backend::TermionBackend;
+    use tui::Terminal;
+
+    pub type Backend = TermionBackend<RawTerminal<std::io::Stdout>>;
+
+    pub fn setup_terminal() -> Result<Terminal<Backend>, Box<dyn Error>> {
+        let raw = stdout().into_raw_mode()?;
+        let terminal = Terminal::new(TermionBackend::new(raw))?;
+        Ok(terminal)
+    }
+
+    pub fn teardown_terminal() -> Result<(), Box<dyn Error>> {
+        // Dropping the `RawTerminal` guard (owned by the backend) restores the
+        // terminal, so there is nothing left to do here.
+        Ok(())
+    }
+
+    pub fn spawn_input_thread(tx: Sender<Event<Key>>, tick_rate: Duration) {
+        let (key_tx, key_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for key in std::io::stdin().keys().flatten() {
+                if key_tx.send(key).is_err() {
+                    break;
+                }
+            }
+        });
+
+        std::thread::spawn(move || {
+            let mut last_tick = Instant::now();
+            loop {
+                let timeout = tick_rate
+                    .checked_sub(last_tick.elapsed())
+                    .unwrap_or_else(|| Duration::from_millis(0));
+
+                if let Ok(key) = key_rx.recv_timeout(timeout) {
+                    let key = match key {
+                        TKey::Char(c) => Key::Char(c),
+                        _ => Key::Other,
+                    };
+                    tx.send(Event::Input(key)).expect("can send events");
+                }
+
+                if last_tick.elapsed() >= tick_rate && tx.send(Event::Tick).is_ok() {
+                    last_tick = Instant::now();
+                }
+            }
+        });
+    }
+}
+
+#[cfg(feature = "rustbox")]
+mod imp {
+    use super::*;
+    use rustbox::{InitOptions, RustBox};
+    use std::sync::{Arc, Mutex, OnceLock};
+    use tui::backend::Backend as TuiBackend;
+    use tui::buffer::Cell;
+    use tui::layout::Rect;
+    use tui::Terminal;
+
+    /// A `tui::backend::Backend` over `rustbox`, since `tui` ships no built-in one.
+    pub struct Backend {
+        rustbox: Arc<Mutex<RustBox>>,
+    }
+
+    /// `rustbox` owns the terminal exclusively, so the input thread polls
+    /// through the same handle the draw loop uses rather than a second
+    /// independent reader. Set once by `setup_terminal`.
+    static HANDLE: OnceLock<Arc<Mutex<RustBox>>> = OnceLock::new();
+
+    impl TuiBackend for Backend {
+        fn draw<'a, I>(&mut self, content: I) -> std::io::Result<()>
+        where
+            I: Iterator<Item = (u16, u16, &'a Cell)>,
+        {
+            let rustbox = self.rustbox.lock().unwrap();
+            for (x, y, cell) in content {
+                rustbox.print(
+                    x as usize,
+                    y as usize,
+                    rustbox::RB_NORMAL,
+                    rustbox::Color::Default,
+                    rustbox::Color::Default,
+                    &cell.symbol,
+                );
+            }
+            Ok(())
+        }
+
+        fn hide_cursor(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        fn show_cursor(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        fn get_cursor(&mut self) -> std::io::Result<(u16, u16)> {
+            Ok((0, 0))
+        }
+
+        fn set_cursor(&mut self, _x: u16, _y: u16) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        fn clear(&mut self) -> std::io::Result<()> {
+            self.rustbox.lock().unwrap().clear();
+            Ok(())
+        }
+
+        fn size(&self) -> std::io::Result<Rect> {
+            let rustbox = self.rustbox.lock().unwrap();
+            Ok(Rect::new(0, 0, rustbox.width() as u16, rustbox.height() as u16))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.rustbox.lock().unwrap().present();
+            Ok(())
+        }
+    }
+
+    pub fn setup_terminal() -> Result<Terminal<Backend>, Box<dyn Error>> {
+        let rustbox = RustBox::init(InitOptions::default())
+            .map_err(|e| -> Box<dyn Error> { format!("{}", e).into() })?;
+        let rustbox = Arc::new(Mutex::new(rustbox));
+        let _ = HANDLE.set(rustbox.clone());
+        let terminal = Terminal::new(Backend { rustbox })?;
+        Ok(terminal)
+    }
+
+    pub fn teardown_terminal() -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    pub fn spawn_input_thread(tx: Sender<Event<Key>>, tick_rate: Duration) {
+        let handle = HANDLE
+            .get()
+            .expect("setup_terminal must run before spawn_input_thread")
+            .clone();
+
+        std::thread::spawn(move || {
+            let mut last_tick = Instant::now();
+            loop {
+                let timeout = tick_rate
+                    .checked_sub(last_tick.elapsed())
+                    .unwrap_or_else(|| Duration::from_millis(0));
+
+                let event = handle.lock().unwrap().peek_event(timeout, false).ok();
+                if let Some(rustbox::Event::KeyEvent(key)) = event {
+                    let key = match key {
+                        rustbox::Key::Char(c) => Key::Char(c),
+                        _ => Key::Other,
+                    };
+                    if tx.send(Event::Input(key)).is_err() {
+                        break;
+                    }
+                }
+
+                if last_tick.elapsed() >= tick_rate {
+                    if tx.send(Event::Tick).is_err() {
+                        break;
+                    }
+                    last_tick = Instant::now();
+                }
+            }
+        });
+    }
+}
+
+pub use imp::*;
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::backend::Key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    StartNext,
+    Save,
+    Reset,
+    Quit,
+    UndoSplit,
+    SkipSplit,
+    ToggleHistory,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawKeymap {
+    start_next: char,
+    save: char,
+    reset: char,
+    quit: char,
+    undo_split: char,
+    skip_split: char,
+    toggle_history: char,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawConfig {
+    tick_rate_ms: u64,
+    splits_path: String,
+    #[serde(default)]
+    live_output_path: Option<String>,
+    keymap: RawKeymap,
+}
+
+/// Runtime configuration loaded from `hotlap.toml`: the draw-loop tick rate,
+/// the default splits file and the action-to-key table the input thread and
+/// the instructions pane consult instead of literal key matches.
+pub struct Config {
+    pub tick_rate: Duration,
+    pub splits_path: String,
+    /// Where to write live timer state for overlays, while a run is active. `None` disables it.
+    pub live_output_path: Option<String>,
+    keymap: HashMap<char, Action>,
+}
+
+impl Config {
+    /// Load `hotlap.toml` from `path`, falling back to built-in defaults if
+    /// it is missing or malformed.
+    pub fn load<T: AsRef<Path>>(path: T) -> Config {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| toml::from_str::<RawConfig>(&contents).ok())
+            .map(Config::from_raw)
+            .unwrap_or_default()
+    }
+
+    fn from_raw(raw: RawConfig) -> Config {
+        let mut keymap = HashMap::new();
+        keymap.insert(raw.keymap.start_next, Action::StartNext);
+        keymap.insert(raw.keymap.save, Action::Save);
+        keymap.insert(raw.keymap.reset, Action::Reset);
+        keymap.insert(raw.keymap.quit, Action::Quit);
+        keymap.insert(raw.keymap.undo_split, Action::UndoSplit);
+        keymap.insert(raw.keymap.skip_split, Action::SkipSplit);
+        keymap.insert(raw.keymap.toggle_history, Action::ToggleHistory);
+
+        Config {
+            tick_rate: Duration::from_millis(raw.tick_rate_ms),
+            splits_path: raw.splits_path,
+            live_output_path: raw.live_output_path,
+            keymap,
+        }
+    }
+
+    /// Resolve a key event to the action bound to it, if any.
+    pub fn action_for(&self, key: Key) -> Option<Action> {
+        match key {
+            Key::Char(c) => self.keymap.get(&c).copied(),
+            Key::Other => None,
+        }
+    }
+
+    /// The key currently bound to `action`, for rendering in the instructions pane.
+    pub fn key_for(&self, action: Action) -> char {
+        self.keymap
+            .iter()
+            .find(|(_, a)| **a == action)
+            .map(|(c, _)| *c)
+            .unwrap_or('?')
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::from_raw(RawConfig {
+            tick_rate_ms: 2,
+            splits_path: String::from("./target/debug/test.json"),
+            live_output_path: None,
+            keymap: RawKeymap {
+                start_next: ' ',
+                save: 's',
+                reset: 'r',
+                quit: 'q',
+                undo_split: 'u',
+                skip_split: 'k',
+                toggle_history: 'h',
+            },
+        })
+    }
+}
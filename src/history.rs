@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Time;
+
+#[derive(Serialize, Deserialize)]
+struct RunRecord {
+    timestamp: u64,
+    splits: Vec<Time>,
+    total: Time,
+}
+
+/// Every completed run, appended to on each finish and persisted to `history.json`.
+#[derive(Serialize, Deserialize, Default)]
+pub struct History {
+    runs: Vec<RunRecord>,
+}
+
+/// Aggregate per-split stats across every recorded run.
+pub struct SplitStats {
+    pub best: f32,
+    pub worst: f32,
+    pub mean: f32,
+    pub attempts: u32,
+}
+
+impl History {
+    pub fn load<T: AsRef<Path>>(path: T) -> History {
+        File::open(path)
+            .ok()
+            .and_then(|f| serde_json::from_reader(BufReader::new(f)).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save<T: AsRef<Path>>(&self, path: T) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string(self)?;
+        let mut f = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        f.write_all(json.as_bytes())?;
+        Ok(())
+    }
+
+    /// Append a finished run: the cumulative time recorded at every split, plus the final total.
+    pub fn record_run(&mut self, splits: Vec<Time>, total: Time) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.runs.push(RunRecord {
+            timestamp,
+            splits,
+            total,
+        });
+    }
+
+    /// Best/worst/mean segment duration and attempt count for each of the
+    /// first `split_count` splits, in split order.
+    pub fn stats(&self, split_count: usize) -> Vec<SplitStats> {
+        (0..split_count)
+            .map(|i| {
+                let durations: Vec<f32> = self
+                    .runs
+                    .iter()
+                    .filter_map(|run| {
+                        let cumulative = run.splits.get(i)?.total_seconds();
+                        let previous = if i == 0 {
+                            0f32
+                        } else {
+                            run.splits.get(i - 1)?.total_seconds()
+                        };
+                        Some(cumulative - previous)
+                    })
+                    .collect();
+
+                if durations.is_empty() {
+                    SplitStats {
+                        best: 0f32,
+                        worst: 0f32,
+                        mean: 0f32,
+                        attempts: 0,
+                    }
+                } else {
+                    let best = durations.iter().cloned().fold(f32::INFINITY, f32::min);
+                    let worst = durations.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+                    let mean = durations.iter().sum::<f32>() / durations.len() as f32;
+                    SplitStats {
+                        best,
+                        worst,
+                        mean,
+                        attempts: durations.len() as u32,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Best/worst/mean final run total and attempt count across every recorded run.
+    pub fn total_stats(&self) -> SplitStats {
+        let durations: Vec<f32> = self.runs.iter().map(|run| run.total.total_seconds()).collect();
+
+        if durations.is_empty() {
+            SplitStats {
+                best: 0f32,
+                worst: 0f32,
+                mean: 0f32,
+                attempts: 0,
+            }
+        } else {
+            let best = durations.iter().cloned().fold(f32::INFINITY, f32::min);
+            let worst = durations.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+            let mean = durations.iter().sum::<f32>() / durations.len() as f32;
+            SplitStats {
+                best,
+                worst,
+                mean,
+                attempts: durations.len() as u32,
+            }
+        }
+    }
+}
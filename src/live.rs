@@ -0,0 +1,35 @@
+use serde::Serialize;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// One split's committed time and delta, for an external overlay to read.
+#[derive(Serialize)]
+pub struct LiveSplit {
+    pub name: String,
+    pub committed_time: Option<f32>,
+    pub delta: Option<f32>,
+}
+
+/// The full state of an active run, written at the tick rate so a
+/// browser-source or overlay can poll it during a stream.
+#[derive(Serialize)]
+pub struct LiveState {
+    pub elapsed: f32,
+    pub current_index: usize,
+    pub current_name: Option<String>,
+    pub finished: bool,
+    pub splits: Vec<LiveSplit>,
+}
+
+pub fn write<T: AsRef<Path>>(path: T, state: &LiveState) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string(state)?;
+    let mut f = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    f.write_all(json.as_bytes())?;
+    Ok(())
+}
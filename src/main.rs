@@ -1,29 +1,30 @@
-use crossterm::{
-    event::{self, Event as CEvent, KeyCode},
-    terminal::{disable_raw_mode, enable_raw_mode},
-};
 use serde::{Deserialize, Serialize};
 use std::error::Error;
-use std::fs::{File, OpenOptions};
-use std::io::{stdout, BufReader, Write};
 use std::path::Path;
 use std::sync::mpsc;
-use std::thread;
 use std::time::{Duration, Instant};
 use tui::{
-    backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
-    Terminal,
 };
+
+use config::{Action, Config};
+use history::History;
+
+mod backend;
+mod config;
+mod history;
+mod live;
+mod splits;
+
 enum Event<T> {
     Input(T),
     Tick,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 struct Time {
     h: i32,
     m: i32,
@@ -31,50 +32,56 @@ struct Time {
     ms: i32,
 }
 
+impl Time {
+    /// `ms` is milliseconds (0-999), matching `parse_millis`'s 4th field.
+    fn total_seconds(&self) -> f32 {
+        (self.h * 60 * 60 + self.m * 60 + self.s) as f32 + self.ms as f32 / 1000f32
+    }
+
+    fn from_seconds(total: f32) -> Time {
+        let total = if total > 0.0 { total } else { 0.0 };
+        let total_ms = (total * 1000f32).round() as i32;
+        let whole = total_ms / 1000;
+        Time {
+            h: whole / (60 * 60),
+            m: (whole / 60) % 60,
+            s: whole % 60,
+            ms: total_ms % 1000,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct Milestone {
     name: String,
     time: Time,
     result: Option<f32>,
+    pb_cumulative: Option<Time>,
+    gold_segment: Option<f32>,
+    #[serde(skip)]
+    is_gold: bool,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let stdout = stdout();
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let config = Config::load(Path::new("hotlap.toml"));
 
+    let mut terminal = backend::setup_terminal()?;
     terminal.clear()?;
 
-    // Channel for keyboard inputs
-    enable_raw_mode().expect("can run in raw mode");
-
     let (tx, rx) = mpsc::channel();
-    let tick_rate = Duration::from_millis(2);
-    thread::spawn(move || {
-        let mut last_tick = Instant::now();
-        loop {
-            let timeout = tick_rate
-                .checked_sub(last_tick.elapsed())
-                .unwrap_or_else(|| Duration::from_millis(0));
-
-            if event::poll(timeout).expect("poll works") {
-                if let CEvent::Key(key) = event::read().expect("can read events") {
-                    tx.send(Event::Input(key)).expect("can send events");
-                }
-            }
-
-            if last_tick.elapsed() >= tick_rate {
-                if let Ok(_) = tx.send(Event::Tick) {
-                    last_tick = Instant::now();
-                }
-            }
-        }
-    });
+    backend::spawn_input_thread(tx, config.tick_rate);
 
     let mut start_time: Option<Instant> = None;
     let mut is_started = false;
-    let mut milestones: Vec<Milestone> = load_json(Path::new("./target/debug/test.json")).unwrap();
+    let mut milestones: Vec<Milestone> =
+        splits::load(Path::new(&config.splits_path)).unwrap_or_default();
     let mut current_idx = 0;
+    // Which splits have actually been completed (not skipped) this run, so a
+    // skipped split's stale `time` can't be used as another split's
+    // `previous_cumulative`.
+    let mut committed: Vec<bool> = vec![false; milestones.len()];
+    let mut history = History::load(Path::new("history.json"));
+    let mut show_history = false;
 
     loop {
         let mut current_time = Time {
@@ -89,6 +96,15 @@ fn main() -> Result<(), Box<dyn Error>> {
             current_time = Time { h, m, s, ms };
         }
 
+        if is_started {
+            if let Some(path) = &config.live_output_path {
+                let _ = live::write(
+                    path,
+                    &live_state(&milestones, &committed, current_idx, &current_time, false),
+                );
+            }
+        }
+
         terminal.draw(|f| {
             // Split window (TOP - BOTTOM)
             let chunks = Layout::default()
@@ -101,7 +117,8 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .constraints(
                     [
                         Constraint::Percentage(10),
-                        Constraint::Percentage(50),
+                        Constraint::Percentage(10),
+                        Constraint::Percentage(40),
                         Constraint::Percentage(40),
                     ]
                     .as_ref(),
@@ -134,30 +151,128 @@ fn main() -> Result<(), Box<dyn Error>> {
             let timer = Paragraph::new(create_span(&current_time)).block(create_block("time"));
             f.render_widget(timer, left_chunks[0]);
 
+            // Session progress: which split we're on, and the live delta
+            // against that split's best time, updated every tick.
+            let progress_text = if milestones.is_empty() {
+                Spans::from("")
+            } else {
+                Spans::from(format!("Split {} / {}", current_idx + 1, milestones.len()))
+            };
+
+            let live_delta = if is_started {
+                milestones
+                    .get(current_idx)
+                    .and_then(|m| m.pb_cumulative)
+                    .map(|pb| current_time.total_seconds() - pb.total_seconds())
+            } else {
+                None
+            };
+
+            let live_delta_span = match live_delta {
+                Some(delta) => {
+                    let style = if delta > 0.0 {
+                        Style::default().fg(Color::Red)
+                    } else if delta < 0.0 {
+                        Style::default().fg(Color::Green)
+                    } else {
+                        Style::default()
+                    };
+                    Span::styled(format!("{:+.3}", delta), style)
+                }
+                None => Span::from(""),
+            };
+
+            let progress =
+                Paragraph::new(vec![progress_text, Spans::from(live_delta_span)])
+                    .block(create_block("progress"));
+            f.render_widget(progress, left_chunks[1]);
+
             // Hotlap instructions pane
             let hotlap_text = vec![
-                Spans::from("<space>: start/next"),
-                Spans::from("s: save best"),
-                Spans::from("r: reset"),
-                Spans::from("q: quit"),
+                Spans::from(format!(
+                    "{}: start/next",
+                    describe_key(config.key_for(Action::StartNext))
+                )),
+                Spans::from(format!("{}: save best", config.key_for(Action::Save))),
+                Spans::from(format!("{}: reset", config.key_for(Action::Reset))),
+                Spans::from(format!("{}: undo split", config.key_for(Action::UndoSplit))),
+                Spans::from(format!("{}: skip split", config.key_for(Action::SkipSplit))),
+                Spans::from(format!("{}: history", config.key_for(Action::ToggleHistory))),
+                Spans::from(format!("{}: quit", config.key_for(Action::Quit))),
             ];
 
             let hotlap = Paragraph::new(hotlap_text.clone()).block(create_block("hotlap"));
 
-            f.render_widget(hotlap, left_chunks[1]);
+            f.render_widget(hotlap, left_chunks[2]);
+
+            // Sum of best: the theoretical best run if every gold segment lined up
+            let sum_of_best = milestones
+                .iter()
+                .try_fold(0f32, |acc, m| m.gold_segment.map(|gold| acc + gold));
+
+            let sum_of_best_span = match sum_of_best {
+                Some(total) => create_span(&Time::from_seconds(total)),
+                None => Span::from("--:--:--.---"),
+            };
 
-            // Mileshtones
-            if milestones.len() > 0 {
+            let sum_of_best_pane =
+                Paragraph::new(sum_of_best_span).block(create_block("sum of best"));
+            f.render_widget(sum_of_best_pane, left_chunks[3]);
+
+            // Mileshtones / history stats
+            if show_history {
+                let stats = history.stats(milestones.len());
+                let mut rows: Vec<Row> = milestones
+                    .iter()
+                    .zip(stats.iter())
+                    .map(|(m, stat)| {
+                        Row::new(vec![
+                            Cell::from(Span::styled(
+                                m.name.clone(),
+                                Style::default().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+                            )),
+                            Cell::from(format!("{:.3}", stat.best)),
+                            Cell::from(format!("{:.3}", stat.worst)),
+                            Cell::from(format!("{:.3}", stat.mean)),
+                            Cell::from(format!("{}", stat.attempts)),
+                        ])
+                    })
+                    .collect();
+
+                let total_stat = history.total_stats();
+                rows.push(Row::new(vec![
+                    Cell::from(Span::styled(
+                        "total",
+                        Style::default().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+                    )),
+                    Cell::from(format!("{:.3}", total_stat.best)),
+                    Cell::from(format!("{:.3}", total_stat.worst)),
+                    Cell::from(format!("{:.3}", total_stat.mean)),
+                    Cell::from(format!("{}", total_stat.attempts)),
+                ]));
+
+                let table = Table::new(rows)
+                    .header(Row::new(vec!["split", "best", "worst", "mean", "attempts"]))
+                    .block(create_block("history"))
+                    .widths(&[
+                        Constraint::Percentage(40),
+                        Constraint::Percentage(15),
+                        Constraint::Percentage(15),
+                        Constraint::Percentage(15),
+                        Constraint::Percentage(15),
+                    ]);
+                f.render_widget(table, right_chunks[0]);
+            } else if !milestones.is_empty() {
                 let mut rows: Vec<Row> = vec![];
 
                 for m in milestones.iter() {
                     let mut row: Vec<Cell> = vec![];
                     row.push(Cell::from(Span::styled(
-                        format!("{}", &m.name),
+                        m.name.clone(),
                         Style::default().add_modifier(Modifier::BOLD | Modifier::ITALIC),
                     )));
 
-                    // +- from last Time
+                    // +- from pb_cumulative at this split
                     match m.result {
                         Some(r) => {
                             let mut style = Style::default();
@@ -172,7 +287,21 @@ fn main() -> Result<(), Box<dyn Error>> {
                         None => row.push(Cell::from("")),
                     };
 
-                    row.push(Cell::from(create_span(&m.time)));
+                    let time_style = if m.is_gold {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    row.push(Cell::from(Span::styled(
+                        format!(
+                            "{}:{}:{}.{}",
+                            format_tens(m.time.h),
+                            format_tens(m.time.m),
+                            format_tens(m.time.s),
+                            format_hundreds(m.time.ms)
+                        ),
+                        time_style,
+                    )));
 
                     rows.push(Row::new(row));
                 }
@@ -192,52 +321,134 @@ fn main() -> Result<(), Box<dyn Error>> {
         })?;
 
         match rx.recv()? {
-            Event::Input(event) => match event.code {
-                KeyCode::Char('q') => {
-                    disable_raw_mode()?;
+            Event::Input(key) => match config.action_for(key) {
+                Some(Action::Quit) => {
+                    backend::teardown_terminal()?;
                     break;
                 }
-                KeyCode::Char(' ') => {
+                Some(Action::StartNext) => {
                     if !is_started {
-                        is_started = true;
-                        current_idx = 0;
-                        start_time = Some(Instant::now());
+                        // Starting against an empty splits file would leave
+                        // `current_idx` indexing nothing the draw loop can read.
+                        if !milestones.is_empty() {
+                            is_started = true;
+                            current_idx = 0;
+                            start_time = Some(Instant::now());
+                            committed = vec![false; milestones.len()];
+                            for m in milestones.iter_mut() {
+                                m.is_gold = false;
+                            }
+                        }
                     } else {
-                        let old_milestone = &milestones[current_idx];
-                        let duration = ((current_time.h * 60 * 60
-                            + current_time.m * 60
-                            + current_time.s) as f32
-                            + current_time.ms as f32 / 100f32)
-                            - ((old_milestone.time.h * 60 * 60
-                                + old_milestone.time.m * 60
-                                + old_milestone.time.s) as f32
-                                + old_milestone.time.ms as f32 / 100f32);
+                        let current_cumulative = current_time.total_seconds();
+                        let predecessor_committed = current_idx == 0 || committed[current_idx - 1];
+                        let previous_cumulative = if current_idx == 0 {
+                            0f32
+                        } else {
+                            milestones[current_idx - 1].time.total_seconds()
+                        };
+                        let segment_duration = current_cumulative - previous_cumulative;
+
+                        let result = milestones[current_idx]
+                            .pb_cumulative
+                            .map(|pb| current_cumulative - pb.total_seconds());
+
+                        // A skipped predecessor leaves `previous_cumulative` stale, so the
+                        // segment duration it implies isn't a real measurement: keep
+                        // whatever gold_segment this split already had instead of
+                        // overwriting it with a bogus one, and don't mark it gold.
+                        let (gold_segment, is_gold) = if predecessor_committed {
+                            match milestones[current_idx].gold_segment {
+                                Some(gold) if segment_duration < gold => {
+                                    (Some(segment_duration), true)
+                                }
+                                Some(gold) => (Some(gold), false),
+                                None => (Some(segment_duration), true),
+                            }
+                        } else {
+                            (milestones[current_idx].gold_segment, false)
+                        };
+
                         let milestone = Milestone {
                             name: String::from(&milestones[current_idx].name),
                             time: current_time,
-                            result: Some(duration),
+                            result,
+                            pb_cumulative: milestones[current_idx].pb_cumulative,
+                            gold_segment,
+                            is_gold,
                         };
                         let _ = std::mem::replace(&mut milestones[current_idx], milestone);
+                        committed[current_idx] = true;
 
                         if current_idx + 1 < milestones.len() {
                             current_idx += 1;
                         } else {
                             is_started = false;
                             start_time = None;
+
+                            history.record_run(
+                                milestones.iter().map(|m| m.time).collect(),
+                                current_time,
+                            );
+                            history.save("history.json")?;
+
+                            if let Some(path) = &config.live_output_path {
+                                let _ = live::write(
+                                    path,
+                                    &live_state(&milestones, &committed, current_idx, &current_time, true),
+                                );
+                            }
+
+                            // A run that beats the total PB becomes the new PB for every split.
+                            let total_pb = milestones
+                                .last()
+                                .and_then(|m| m.pb_cumulative)
+                                .map(|t| t.total_seconds());
+                            let is_new_pb = match total_pb {
+                                Some(pb) => current_cumulative < pb,
+                                None => true,
+                            };
+                            if is_new_pb {
+                                // A skipped split's `time` is stale, not a real result for
+                                // this run, so it shouldn't be adopted as the new PB either.
+                                for (i, m) in milestones.iter_mut().enumerate() {
+                                    if committed[i] {
+                                        m.pb_cumulative = Some(m.time);
+                                    }
+                                }
+                            }
                         }
                     }
                 }
-                KeyCode::Char('r') => {
-                    milestones = load_json(Path::new("")).unwrap();
+                Some(Action::Reset) => {
+                    milestones =
+                        splits::load(Path::new(&config.splits_path)).unwrap_or_default();
                     is_started = false;
                     start_time = None;
+                    committed = vec![false; milestones.len()];
                 }
-                KeyCode::Char('s') => {
-                    if !is_started {
-                        save_json("./target/debug/test.json", &milestones)?;
+                Some(Action::Save) if !is_started => {
+                    splits::save(&config.splits_path, &milestones)?;
+                }
+                Some(Action::Save) => {}
+                Some(Action::SkipSplit) if is_started => {
+                    if current_idx + 1 < milestones.len() {
+                        current_idx += 1;
+                    } else {
+                        is_started = false;
+                        start_time = None;
                     }
                 }
-                _ => {}
+                Some(Action::SkipSplit) => {}
+                Some(Action::UndoSplit) if is_started && current_idx > 0 => {
+                    current_idx -= 1;
+                    committed[current_idx] = false;
+                }
+                Some(Action::UndoSplit) => {}
+                Some(Action::ToggleHistory) => {
+                    show_history = !show_history;
+                }
+                None => {}
             },
             Event::Tick => {}
         }
@@ -245,6 +456,50 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn live_state(
+    milestones: &[Milestone],
+    committed: &[bool],
+    current_idx: usize,
+    current_time: &Time,
+    finished: bool,
+) -> live::LiveState {
+    live::LiveState {
+        elapsed: current_time.total_seconds(),
+        current_index: current_idx,
+        current_name: if finished {
+            None
+        } else {
+            milestones.get(current_idx).map(|m| m.name.clone())
+        },
+        finished,
+        splits: milestones
+            .iter()
+            .enumerate()
+            .map(|(i, m)| live::LiveSplit {
+                name: m.name.clone(),
+                committed_time: if committed.get(i) == Some(&true) {
+                    Some(m.time.total_seconds())
+                } else {
+                    None
+                },
+                delta: if committed.get(i) == Some(&true) {
+                    m.result
+                } else {
+                    None
+                },
+            })
+            .collect(),
+    }
+}
+
+fn describe_key(key: char) -> String {
+    if key == ' ' {
+        String::from("<space>")
+    } else {
+        key.to_string()
+    }
+}
+
 fn parse_millis(duration: Duration) -> (i32, i32, i32, i32) {
     let millis = duration.as_millis();
     let hours = (millis / (1000 * 60 * 60)) % 24;
@@ -272,23 +527,3 @@ fn format_hundreds(digit: i32) -> String {
         format!("{}", digit)
     }
 }
-
-fn load_json<T: AsRef<Path>>(path: T) -> Result<Vec<Milestone>, Box<dyn Error>> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let milestones = serde_json::from_reader(reader)?;
-    Ok(milestones)
-}
-
-fn save_json<T: AsRef<Path>>(path: T, milestones: &Vec<Milestone>) -> std::io::Result<()> {
-    let json = serde_json::to_string(milestones)?;
-
-    let mut f = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)?;
-    f.write_all(&json.as_bytes())?;
-
-    Ok(())
-}
@@ -0,0 +1,229 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::{format_hundreds, format_tens, Milestone, Time};
+
+/// Load a splits file, picking the format from the path's extension
+/// (`.lss` for LiveSplit XML, everything else falls back to JSON).
+pub fn load<T: AsRef<Path>>(path: T) -> Result<Vec<Milestone>, Box<dyn Error>> {
+    match path.as_ref().extension().and_then(|e| e.to_str()) {
+        Some("lss") => load_lss(path),
+        _ => load_json(path),
+    }
+}
+
+/// Save a splits file, picking the format from the path's extension
+/// (`.lss` for LiveSplit XML, everything else falls back to JSON).
+pub fn save<T: AsRef<Path>>(path: T, milestones: &[Milestone]) -> Result<(), Box<dyn Error>> {
+    match path.as_ref().extension().and_then(|e| e.to_str()) {
+        Some("lss") => save_lss(path, milestones),
+        _ => Ok(save_json(path, milestones)?),
+    }
+}
+
+pub fn load_json<T: AsRef<Path>>(path: T) -> Result<Vec<Milestone>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let reader = std::io::BufReader::new(file);
+    let milestones = serde_json::from_reader(reader)?;
+    Ok(milestones)
+}
+
+pub fn save_json<T: AsRef<Path>>(path: T, milestones: &[Milestone]) -> std::io::Result<()> {
+    let json = serde_json::to_string(milestones)?;
+
+    let mut f = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    f.write_all(json.as_bytes())?;
+
+    Ok(())
+}
+
+/// Load milestones from a LiveSplit `.lss` splits file, mapping each
+/// `<Segment>`'s `<Name>` to `Milestone.name`, the "Personal Best" comparison's
+/// `<SplitTime><RealTime>` to `pb_cumulative` and `<BestSegmentTime><RealTime>`
+/// to `gold_segment`.
+pub fn load_lss<T: AsRef<Path>>(path: T) -> Result<Vec<Milestone>, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut reader = Reader::from_str(&contents);
+    reader.trim_text(true);
+
+    let mut milestones = vec![];
+    let mut buf = Vec::new();
+    let mut tag_stack: Vec<String> = vec![];
+
+    let mut in_split_times = false;
+    let mut in_best_segment = false;
+    let mut is_pb_split_time = false;
+
+    let mut name = String::new();
+    let mut pb_cumulative: Option<Time> = None;
+    let mut gold_segment: Option<f32> = None;
+
+    loop {
+        match reader.read_event(&mut buf)? {
+            Event::Start(ref e) => {
+                let tag = String::from_utf8(e.name().to_vec())?;
+                match tag.as_str() {
+                    "Segment" => {
+                        name = String::new();
+                        pb_cumulative = None;
+                        gold_segment = None;
+                    }
+                    "SplitTimes" => in_split_times = true,
+                    "BestSegmentTime" => in_best_segment = true,
+                    "SplitTime" if in_split_times => {
+                        is_pb_split_time = e.attributes().flatten().any(|a| {
+                            a.key == b"name"
+                                && a.unescaped_value()
+                                    .is_ok_and(|v| v.as_ref() == b"Personal Best")
+                        });
+                    }
+                    _ => {}
+                }
+                tag_stack.push(tag);
+            }
+            Event::Text(e) => {
+                let text = e.unescape_and_decode(&reader)?;
+                match tag_stack.last().map(|s| s.as_str()) {
+                    Some("Name") if tag_stack.len() >= 2 => name = text,
+                    Some("RealTime") if in_best_segment => {
+                        gold_segment = parse_lss_time(&text).map(|t| t.total_seconds());
+                    }
+                    Some("RealTime") if in_split_times && is_pb_split_time => {
+                        pb_cumulative = parse_lss_time(&text);
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(ref e) => {
+                let tag = String::from_utf8(e.name().to_vec())?;
+                tag_stack.pop();
+                match tag.as_str() {
+                    "SplitTimes" => in_split_times = false,
+                    "BestSegmentTime" => in_best_segment = false,
+                    "Segment" => milestones.push(Milestone {
+                        name: name.clone(),
+                        time: Time {
+                            h: 0,
+                            m: 0,
+                            s: 0,
+                            ms: 0,
+                        },
+                        result: None,
+                        pb_cumulative,
+                        gold_segment,
+                        is_gold: false,
+                    }),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(milestones)
+}
+
+/// Save milestones as a minimal LiveSplit `.lss` splits file: one `<Segment>`
+/// per milestone, its `pb_cumulative` as the "Personal Best" comparison and
+/// its `gold_segment` as the `BestSegmentTime`.
+pub fn save_lss<T: AsRef<Path>>(path: T, milestones: &[Milestone]) -> Result<(), Box<dyn Error>> {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<Run version=\"1.7.0\">\n");
+    xml.push_str("  <Segments>\n");
+
+    for m in milestones.iter() {
+        xml.push_str("    <Segment>\n");
+        xml.push_str(&format!("      <Name>{}</Name>\n", m.name));
+        xml.push_str("      <SplitTimes>\n");
+        xml.push_str("        <SplitTime name=\"Personal Best\">\n");
+        if let Some(pb) = m.pb_cumulative {
+            xml.push_str(&format!(
+                "          <RealTime>{}</RealTime>\n",
+                format_lss_time(&pb)
+            ));
+        }
+        xml.push_str("        </SplitTime>\n");
+        xml.push_str("      </SplitTimes>\n");
+        if let Some(gold) = m.gold_segment {
+            xml.push_str("      <BestSegmentTime>\n");
+            xml.push_str(&format!(
+                "        <RealTime>{}</RealTime>\n",
+                format_lss_time(&Time::from_seconds(gold))
+            ));
+            xml.push_str("      </BestSegmentTime>\n");
+        }
+        xml.push_str("    </Segment>\n");
+    }
+
+    xml.push_str("  </Segments>\n");
+    xml.push_str("</Run>\n");
+
+    let mut f = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    f.write_all(xml.as_bytes())?;
+
+    Ok(())
+}
+
+fn format_lss_time(t: &Time) -> String {
+    format!(
+        "{}:{}:{}.{}",
+        format_tens(t.h),
+        format_tens(t.m),
+        format_tens(t.s),
+        format_hundreds(t.ms)
+    )
+}
+
+/// Parse a .NET `TimeSpan` as LiveSplit writes it in `RealTime` elements:
+/// `[d.]HH:MM:SS[.fffffff]`, an optional leading day field and up to 7
+/// fractional-second digits. `Time.ms` holds milliseconds, so the fraction
+/// is scaled by however many digits it actually has rather than assumed to
+/// already be in milliseconds.
+fn parse_lss_time(s: &str) -> Option<Time> {
+    let (whole, fraction) = match s.rfind('.') {
+        Some(dot) if s[dot + 1..].chars().all(|c| c.is_ascii_digit()) && s[..dot].contains(':') => {
+            (&s[..dot], &s[dot + 1..])
+        }
+        _ => (s, "0"),
+    };
+
+    let mut parts = whole.split(':');
+    let first = parts.next()?;
+    let m: i32 = parts.next()?.parse().ok()?;
+    let s: i32 = parts.next()?.parse().ok()?;
+
+    let (day, h): (i32, i32) = match first.split_once('.') {
+        Some((d, h)) => (d.parse().ok()?, h.parse().ok()?),
+        None => (0, first.parse().ok()?),
+    };
+
+    let fraction_value: u64 = fraction.parse().ok()?;
+    let fraction_seconds = fraction_value as f64 / 10f64.powi(fraction.len() as i32);
+    let fraction_ms = (fraction_seconds * 1000f64).round() as i64;
+
+    // A fraction that rounds up to a whole second carries into the seconds.
+    let total_ms =
+        (((day as i64 * 24 + h as i64) * 60 + m as i64) * 60 + s as i64) * 1000 + fraction_ms;
+    let whole = total_ms / 1000;
+    Some(Time {
+        h: (whole / (60 * 60)) as i32,
+        m: ((whole / 60) % 60) as i32,
+        s: (whole % 60) as i32,
+        ms: (total_ms % 1000) as i32,
+    })
+}